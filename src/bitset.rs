@@ -0,0 +1,170 @@
+//! Bitset-backed visited/frontier tracking and adjacency testing, for graphs large
+//! enough that growing a `HashSet<NodeIx>` per `bfs`/`connected`/`subgraph` call starts
+//! to dominate runtime.
+
+use crate::{Graph, NodeIx};
+
+/// A growable bitset over small non-negative indices, backed by a `Vec<u64>`.
+#[derive(Default, Clone)]
+pub struct BitVector {
+    words: Vec<u64>,
+}
+
+impl BitVector {
+    pub fn new() -> BitVector {
+        BitVector { words: Vec::new() }
+    }
+
+    pub fn with_capacity(bits: usize) -> BitVector {
+        BitVector {
+            words: vec![0u64; bits.div_ceil(64)],
+        }
+    }
+
+    /// Set bit `idx`, growing the backing storage if needed. Returns whether the bit
+    /// was previously unset.
+    pub fn insert(&mut self, idx: usize) -> bool {
+        let word = idx / 64;
+        let mask = 1u64 << (idx % 64);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let changed = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        changed
+    }
+
+    pub fn contains(&self, idx: usize) -> bool {
+        let word = idx / 64;
+        let mask = 1u64 << (idx % 64);
+        self.words.get(word).is_some_and(|w| w & mask != 0)
+    }
+
+    /// Iterate the set bits in ascending order, skipping whole words that are empty.
+    pub fn iter(&self) -> BitVectorIter<'_> {
+        BitVectorIter {
+            words: &self.words,
+            word_idx: 0,
+            cur: 0,
+        }
+    }
+
+    /// OR `other` into `self`, growing `self` if `other` is wider. Returns whether any
+    /// bit was newly set.
+    pub fn union_with(&mut self, other: &BitVector) -> bool {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        let mut changed = false;
+        for (word, &other_word) in self.words.iter_mut().zip(other.words.iter()) {
+            let merged = *word | other_word;
+            changed |= merged != *word;
+            *word = merged;
+        }
+        changed
+    }
+}
+
+pub struct BitVectorIter<'a> {
+    words: &'a [u64],
+    word_idx: usize,
+    cur: u64,
+}
+
+impl<'a> Iterator for BitVectorIter<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        while self.cur == 0 {
+            if self.word_idx >= self.words.len() {
+                return None;
+            }
+            self.cur = self.words[self.word_idx];
+            self.word_idx += 1;
+        }
+        let bit = self.cur.trailing_zeros() as usize;
+        self.cur &= self.cur - 1;
+        Some((self.word_idx - 1) * 64 + bit)
+    }
+}
+
+/// A row-major adjacency bitmatrix: `words_per_row` `u64`s per node, so a pure
+/// existence test for "is `a` adjacent to `b`" (via `is_adjacent`) is a single bit
+/// lookup instead of a scan over edge lists. `direct_connection` still needs to return
+/// the edge's weight, which this matrix doesn't store, so it keeps its degree-bounded
+/// scan rather than going through here.
+pub struct BitMatrix {
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl BitMatrix {
+    fn new(n: usize) -> BitMatrix {
+        let words_per_row = n.div_ceil(64);
+        BitMatrix {
+            words_per_row,
+            bits: vec![0u64; words_per_row * n],
+        }
+    }
+
+    fn set(&mut self, row: usize, col: usize) {
+        let word = col / 64;
+        let mask = 1u64 << (col % 64);
+        self.bits[row * self.words_per_row + word] |= mask;
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        let word = col / 64;
+        let mask = 1u64 << (col % 64);
+        self.bits[row * self.words_per_row + word] & mask != 0
+    }
+}
+
+impl<'s> Graph<'s> {
+    /// Build the full adjacency bitmatrix once, so repeated direct-adjacency queries
+    /// (via `is_adjacent`) are O(1) bit lookups instead of scans over each node's edge
+    /// list.
+    pub fn adjacency_bits(&self) -> BitMatrix {
+        let mut m = BitMatrix::new(self.nodes.len());
+        for edge in &self.edges {
+            m.set(edge.a.0 as usize, edge.b.0 as usize);
+            m.set(edge.b.0 as usize, edge.a.0 as usize);
+        }
+        m
+    }
+
+    /// O(1) direct-adjacency test backed by a precomputed `adjacency_bits` matrix.
+    pub fn is_adjacent(&self, a: NodeIx, b: NodeIx, adjacency: &BitMatrix) -> bool {
+        adjacency.contains(a.0 as usize, b.0 as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+
+    #[test]
+    fn is_adjacent_matches_direct_connection() {
+        let g = Graph::from_edge_list("a,b,1\nb,c,1").unwrap();
+        let (a, b, c) = (g.map["a"], g.map["b"], g.map["c"]);
+        let adjacency = g.adjacency_bits();
+
+        assert!(g.is_adjacent(a, b, &adjacency));
+        assert!(g.is_adjacent(b, a, &adjacency), "adjacency is undirected");
+        assert!(!g.is_adjacent(a, c, &adjacency));
+
+        for (x, y) in [(a, b), (b, a), (a, c), (c, a), (b, c), (c, b)] {
+            assert_eq!(g.is_adjacent(x, y, &adjacency), g.direct_connection(x, y).is_some());
+        }
+    }
+
+    #[test]
+    fn connected_with_matches_connected_at_depth_one() {
+        let g = Graph::from_edge_list("a,b,1\nb,c,1").unwrap();
+        let (a, b, c) = (g.map["a"], g.map["b"], g.map["c"]);
+        let adjacency = g.adjacency_bits();
+
+        assert!(g.connected_with(a, b, 1, &adjacency));
+        assert!(!g.connected_with(a, c, 1, &adjacency));
+        assert!(g.connected_with(a, c, 3, &adjacency), "depth > 1 falls back to BFS");
+    }
+}
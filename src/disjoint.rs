@@ -1,4 +1,7 @@
 //! A disjoint set using the union-find algorithm with path-compression
+//!
+//! A second, rollback-capable mode is also available via `DisjointSet::new_rollback`,
+//! for offline dynamic connectivity queries that need to undo recent unions.
 
 use std::cell::Cell;
 use std::cmp::Ordering;
@@ -10,9 +13,30 @@ struct SetElement<T> {
     parent: Cell<usize>,
 }
 
+/// Which union/find strategy a `DisjointSet` was constructed with. Path compression and
+/// rollback are mutually exclusive: compression destroys the parent history that
+/// rollback depends on.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Mode {
+    Compressed,
+    Rollback,
+}
+
+/// One undoable `union_rollback` step: the root that was reparented, its parent
+/// immediately beforehand (always itself, since it was a root), the root it was
+/// attached under, and that root's rank before any increment.
+struct HistoryRecord {
+    child_root: usize,
+    old_parent_of_child: usize,
+    touched_root: usize,
+    old_rank: u32,
+}
+
 pub struct DisjointSet<T> {
     elements: Vec<SetElement<T>>,
     components: Cell<usize>,
+    mode: Mode,
+    history: Vec<HistoryRecord>,
 }
 
 impl<T> Default for DisjointSet<T> {
@@ -20,6 +44,8 @@ impl<T> Default for DisjointSet<T> {
         DisjointSet {
             elements: Vec::new(),
             components: Cell::new(0),
+            mode: Mode::Compressed,
+            history: Vec::new(),
         }
     }
 }
@@ -37,6 +63,19 @@ impl<T> DisjointSet<T> {
         DisjointSet {
             elements: Vec::new(),
             components: Cell::new(0),
+            mode: Mode::Compressed,
+            history: Vec::new(),
+        }
+    }
+
+    /// Construct a `DisjointSet` in rollback mode: `union_rollback`/`rollback` are
+    /// available, but `union` (which relies on path compression) is not.
+    pub fn new_rollback() -> DisjointSet<T> {
+        DisjointSet {
+            elements: Vec::new(),
+            components: Cell::new(0),
+            mode: Mode::Rollback,
+            history: Vec::new(),
         }
     }
 
@@ -72,8 +111,8 @@ impl<T> DisjointSet<T> {
         }
 
         // id is the representative element, return
-        if ptr == id {
-            return id;
+        if ptr == id || self.mode == Mode::Rollback {
+            return ptr;
         }
 
         // perform path compression
@@ -102,6 +141,10 @@ impl<T> DisjointSet<T> {
     }
 
     pub fn union<F: Fn(T, T) -> T>(&mut self, f: F, a: Element, b: Element) {
+        assert!(
+            self.mode == Mode::Compressed,
+            "union requires a DisjointSet::new() instance; use union_rollback on a rollback set"
+        );
         let pa = self.find_set(a.0);
         let pb = self.find_set(b.0);
 
@@ -133,6 +176,69 @@ impl<T> DisjointSet<T> {
         }
     }
 
+    /// Union `a` and `b` without path compression, recording enough history to undo the
+    /// merge with `rollback`. Only valid on a `DisjointSet::new_rollback()` instance.
+    pub fn union_rollback(&mut self, a: Element, b: Element) {
+        assert!(
+            self.mode == Mode::Rollback,
+            "union_rollback requires a DisjointSet::new_rollback() instance"
+        );
+        let pa = self.find_set(a.0);
+        let pb = self.find_set(b.0);
+
+        if pa == pb {
+            return;
+        }
+
+        let rank_cmp = self.elements[pa].rank.cmp(&self.elements[pb].rank);
+        let (child_root, touched_root) = match rank_cmp {
+            Ordering::Less => (pa, pb),
+            _ => (pb, pa),
+        };
+
+        let old_parent_of_child = self.elements[child_root].parent.get();
+        let old_rank = self.elements[touched_root].rank.get();
+
+        self.elements[child_root].parent.replace(touched_root);
+        // Union-by-rank only grows the attached-under root's rank when the merge was a
+        // tie; otherwise the taller tree's height already bounds the result.
+        if rank_cmp == Ordering::Equal {
+            self.elements[touched_root].rank.replace(old_rank + 1);
+        }
+
+        self.components.replace(self.components.get() - 1);
+        self.history.push(HistoryRecord {
+            child_root,
+            old_parent_of_child,
+            touched_root,
+            old_rank,
+        });
+    }
+
+    /// Return a handle to the current point in the union history, for later `rollback`.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undo `union_rollback` calls made since `snapshot`, restoring parents, ranks, and
+    /// the component count to how they were at that point.
+    pub fn rollback(&mut self, to: usize) {
+        assert!(
+            self.mode == Mode::Rollback,
+            "rollback requires a DisjointSet::new_rollback() instance"
+        );
+        while self.history.len() > to {
+            let record = self.history.pop().expect("snapshot is ahead of history");
+            self.elements[record.child_root]
+                .parent
+                .replace(record.old_parent_of_child);
+            self.elements[record.touched_root]
+                .rank
+                .replace(record.old_rank);
+            self.components.replace(self.components.get() + 1);
+        }
+    }
+
     pub fn partition(&self) -> Vec<&T> {
         let mut v = HashSet::new();
 
@@ -158,4 +264,48 @@ impl<T: std::fmt::Debug> std::fmt::Debug for DisjointSet<T> {
         }
         writeln!(f, "}}")
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_rollback_only_bumps_rank_on_a_tie() {
+        let mut ds: DisjointSet<u32> = DisjointSet::new_rollback();
+        let a = ds.singleton(1);
+        let b = ds.singleton(2);
+        let c = ds.singleton(3);
+
+        // a, b start at rank 0: a tie, so the attached-under root's rank grows.
+        ds.union_rollback(a, b);
+        let root_ab = ds.find_set(a.0);
+        assert_eq!(ds.elements[root_ab].rank.get(), 1);
+
+        // The combined {a, b} tree (rank 1) absorbs singleton c (rank 0): not a tie,
+        // so the rank must stay put even though a merge happened.
+        ds.union_rollback(a, c);
+        let root_abc = ds.find_set(a.0);
+        assert_eq!(ds.elements[root_abc].rank.get(), 1);
+    }
+
+    #[test]
+    fn snapshot_and_rollback_restore_prior_state() {
+        let mut ds: DisjointSet<u32> = DisjointSet::new_rollback();
+        let a = ds.singleton(1);
+        let b = ds.singleton(2);
+        let c = ds.singleton(3);
+
+        assert_eq!(ds.len(), 3);
+        let snap = ds.snapshot();
+
+        ds.union_rollback(a, b);
+        ds.union_rollback(a, c);
+        assert_eq!(ds.len(), 1);
+        assert_eq!(ds.find_set(a.0), ds.find_set(c.0));
+
+        ds.rollback(snap);
+        assert_eq!(ds.len(), 3);
+        assert_ne!(ds.find_set(a.0), ds.find_set(b.0));
+        assert_ne!(ds.find_set(a.0), ds.find_set(c.0));
+    }
+}
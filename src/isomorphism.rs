@@ -0,0 +1,214 @@
+//! VF2-style subgraph isomorphism, for comparing MCODE-predicted complexes against
+//! reference complexes regardless of node labeling.
+
+use crate::{Graph, NodeIx};
+use std::collections::HashMap;
+
+impl<'s> Graph<'s> {
+    /// Whether `self` and `other` are isomorphic (same size, and a bijective mapping
+    /// between them preserves every edge).
+    pub fn is_isomorphic(&self, other: &Graph) -> bool {
+        self.nodes.len() == other.nodes.len()
+            && self.edges.len() == other.edges.len()
+            && !other.subgraph_isomorphism_matches(self).is_empty()
+    }
+
+    /// All mappings of `pattern`'s nodes onto a subgraph of `self` that preserve every
+    /// edge (and non-edge) among mapped pairs, regardless of node labeling. Keys are
+    /// `pattern`'s node indices, values are `self`'s.
+    pub fn subgraph_isomorphism_matches(&self, pattern: &Graph) -> Vec<HashMap<NodeIx, NodeIx>> {
+        self.subgraph_isomorphism_matches_with(pattern, |_, _| true, None)
+    }
+
+    /// `subgraph_isomorphism_matches` with an optional node predicate (e.g. requiring
+    /// matching protein ids) and an optional edge-weight tolerance (the max allowed
+    /// difference between a pattern edge's weight and its matched target edge's).
+    pub fn subgraph_isomorphism_matches_with(
+        &self,
+        pattern: &Graph,
+        node_predicate: impl Fn(&str, &str) -> bool,
+        weight_tolerance: Option<u16>,
+    ) -> Vec<HashMap<NodeIx, NodeIx>> {
+        let mut matches = Vec::new();
+        let mut mapping = HashMap::new();
+        let mut reverse = HashMap::new();
+        self.vf2_extend(
+            pattern,
+            &mut mapping,
+            &mut reverse,
+            &node_predicate,
+            weight_tolerance,
+            &mut matches,
+        );
+        matches
+    }
+
+    fn vf2_extend(
+        &self,
+        pattern: &Graph,
+        mapping: &mut HashMap<NodeIx, NodeIx>,
+        reverse: &mut HashMap<NodeIx, NodeIx>,
+        node_predicate: &impl Fn(&str, &str) -> bool,
+        weight_tolerance: Option<u16>,
+        out: &mut Vec<HashMap<NodeIx, NodeIx>>,
+    ) {
+        if mapping.len() == pattern.nodes.len() {
+            out.push(mapping.clone());
+            return;
+        }
+
+        // The frontier is each graph's mapped nodes' unmapped neighbors; VF2 only
+        // extends along it once it's non-empty, which keeps the search connected to
+        // what's already been matched.
+        let pattern_frontier: Vec<NodeIx> = (0..pattern.nodes.len() as u32)
+            .map(NodeIx)
+            .filter(|n| !mapping.contains_key(n))
+            .filter(|&n| pattern.neighbors(n).any(|nb| mapping.contains_key(&nb)))
+            .collect();
+
+        let next_pattern_node = match pattern_frontier.first() {
+            Some(&n) => n,
+            None => (0..pattern.nodes.len() as u32)
+                .map(NodeIx)
+                .find(|n| !mapping.contains_key(n))
+                .expect("mapping.len() < pattern.nodes.len() guarantees an unmapped node"),
+        };
+
+        let target_candidates: Vec<NodeIx> = if pattern_frontier.is_empty() {
+            (0..self.nodes.len() as u32)
+                .map(NodeIx)
+                .filter(|n| !reverse.contains_key(n))
+                .collect()
+        } else {
+            let target_frontier: Vec<NodeIx> = (0..self.nodes.len() as u32)
+                .map(NodeIx)
+                .filter(|n| !reverse.contains_key(n))
+                .filter(|&n| self.neighbors(n).any(|nb| reverse.contains_key(&nb)))
+                .collect();
+            if pattern_frontier.len() > target_frontier.len() {
+                return;
+            }
+            target_frontier
+        };
+
+        for target_node in target_candidates {
+            if !self.feasible(
+                pattern,
+                next_pattern_node,
+                target_node,
+                mapping,
+                node_predicate,
+                weight_tolerance,
+            ) {
+                continue;
+            }
+
+            mapping.insert(next_pattern_node, target_node);
+            reverse.insert(target_node, next_pattern_node);
+            self.vf2_extend(pattern, mapping, reverse, node_predicate, weight_tolerance, out);
+            mapping.remove(&next_pattern_node);
+            reverse.remove(&target_node);
+        }
+    }
+
+    /// Degree compatibility, the node predicate, and consistency of every edge (and
+    /// non-edge) between `pattern_node` and already-mapped pattern nodes.
+    fn feasible(
+        &self,
+        pattern: &Graph,
+        pattern_node: NodeIx,
+        target_node: NodeIx,
+        mapping: &HashMap<NodeIx, NodeIx>,
+        node_predicate: &impl Fn(&str, &str) -> bool,
+        weight_tolerance: Option<u16>,
+    ) -> bool {
+        if pattern.node(pattern_node).edges.len() > self.node(target_node).edges.len() {
+            return false;
+        }
+        if !node_predicate(pattern.node(pattern_node).id, self.node(target_node).id) {
+            return false;
+        }
+
+        for (&mapped_pattern, &mapped_target) in mapping.iter() {
+            let pattern_edge = pattern.direct_connection(pattern_node, mapped_pattern);
+            let target_edge = self.direct_connection(target_node, mapped_target);
+            match (pattern_edge, target_edge) {
+                (None, None) => {}
+                (Some(_), None) | (None, Some(_)) => return false,
+                (Some(pw), Some(tw)) => {
+                    if let Some(tolerance) = weight_tolerance {
+                        if pw.abs_diff(tw) > tolerance {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+
+    #[test]
+    fn triangle_matches_triangle_any_labeling() {
+        let target = Graph::from_edge_list("a,b,1\nb,c,1\nc,a,1\nc,d,1").unwrap();
+        let pattern = Graph::from_edge_list("x,y,1\ny,z,1\nz,x,1").unwrap();
+        let matches = target.subgraph_isomorphism_matches(&pattern);
+        assert_eq!(matches.len(), 6, "3 rotations x 2 reflections of a triangle");
+    }
+
+    #[test]
+    fn disconnected_pattern_matches_disconnected_target() {
+        // Pattern is two disjoint edges; target has the same two edges plus some
+        // unrelated structure, exercising the "no frontier" new-component branch.
+        let target = Graph::from_edge_list("a,b,1\nc,d,1\nd,e,1").unwrap();
+        let pattern = Graph::from_edge_list("w,x,1\ny,z,1").unwrap();
+        let matches = target.subgraph_isomorphism_matches(&pattern);
+        assert!(!matches.is_empty());
+        for m in &matches {
+            assert_eq!(m.len(), 4);
+        }
+    }
+
+    #[test]
+    fn path_does_not_match_triangle() {
+        let target = Graph::from_edge_list("a,b,1\nb,c,1\nc,d,1").unwrap();
+        let pattern = Graph::from_edge_list("x,y,1\ny,z,1\nz,x,1").unwrap();
+        assert!(target.subgraph_isomorphism_matches(&pattern).is_empty());
+    }
+
+    #[test]
+    fn is_isomorphic_requires_same_size() {
+        let a = Graph::from_edge_list("a,b,1\nb,c,1\nc,a,1").unwrap();
+        let b = Graph::from_edge_list("x,y,1\ny,z,1\nz,x,1").unwrap();
+        assert!(a.is_isomorphic(&b));
+
+        let c = Graph::from_edge_list("x,y,1\ny,z,1\nz,x,1\nz,w,1").unwrap();
+        assert!(!a.is_isomorphic(&c));
+    }
+
+    #[test]
+    fn node_predicate_and_weight_tolerance_filter_matches() {
+        let target = Graph::from_edge_list("a,b,5\nb,c,5\nc,a,5").unwrap();
+        let pattern = Graph::from_edge_list("a,b,5\nb,c,5\nc,a,5").unwrap();
+        let matches = target.subgraph_isomorphism_matches_with(&pattern, |p, t| p == t, Some(0));
+        assert_eq!(matches.len(), 1);
+
+        let pattern2 = Graph::from_edge_list("x,y,5\ny,z,5\nz,x,5").unwrap();
+        let matches2 =
+            target.subgraph_isomorphism_matches_with(&pattern2, |p, t| p == t, Some(0));
+        assert!(matches2.is_empty());
+
+        // Same labels, but one edge's weight is 2 away from its target's: outside a
+        // tolerance of 1.
+        let pattern3 = Graph::from_edge_list("a,b,7\nb,c,5\nc,a,5").unwrap();
+        let matches3 =
+            target.subgraph_isomorphism_matches_with(&pattern3, |p, t| p == t, Some(1));
+        assert!(matches3.is_empty());
+    }
+}
@@ -1,5 +1,11 @@
+pub mod bitset;
+pub mod dijkstra;
 pub mod disjoint;
-use disjoint::DisjointSet;
+pub mod isomorphism;
+pub mod mcode;
+pub mod mst;
+pub mod parsers;
+use bitset::BitVector;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::io;
@@ -67,20 +73,29 @@ impl<'s> Graph<'s> {
 
     /// Perform a BFS search, visiting nodes up to `depth` links away from the root
     /// Return a set of visited NodeIx's
-    pub fn bfs(&self, node: NodeIx, mut depth: usize) -> HashSet<NodeIx> {
+    pub fn bfs(&self, node: NodeIx, depth: usize) -> HashSet<NodeIx> {
+        self.bfs_bits(node, depth)
+            .iter()
+            .map(|idx| NodeIx(idx as u32))
+            .collect()
+    }
+
+    /// `bfs`'s bitset-backed core, for callers (like `connected`) that only need
+    /// membership tests and shouldn't pay to collect the result into a `HashSet`.
+    fn bfs_bits(&self, node: NodeIx, mut depth: usize) -> BitVector {
         let mut queue = VecDeque::new();
-        let mut visited = HashSet::new();
+        let mut visited = BitVector::with_capacity(self.nodes.len());
         queue.push_back(node);
 
         while let Some(node_id) = queue.pop_front() {
-            visited.insert(node_id);
+            visited.insert(node_id.0 as usize);
             let root = self.node(node_id);
             for edge in root.edges.iter().map(|ix| self.edge(*ix)) {
-                if visited.insert(edge.a) {
+                if visited.insert(edge.a.0 as usize) {
                     queue.push_back(edge.a);
                 }
 
-                if visited.insert(edge.b) {
+                if visited.insert(edge.b.0 as usize) {
                     queue.push_back(edge.b);
                 }
             }
@@ -93,10 +108,17 @@ impl<'s> Graph<'s> {
     }
 
     pub fn subgraph(&self, node: NodeIx) -> Graph<'_> {
-        let mut g = Graph::default();
         let set = self.bfs(node, 0);
+        self.induced_subgraph(&set)
+    }
+
+    /// Build the subgraph induced by `set`: every edge of `self` whose endpoints are both
+    /// in `set`, together with those endpoints. A node of `set` with no edge inside `set`
+    /// is not included.
+    fn induced_subgraph(&self, set: &HashSet<NodeIx>) -> Graph<'_> {
+        let mut g = Graph::default();
         let mut seen = HashSet::new();
-        for &node_id in &set {
+        for &node_id in set {
             let node = self.node(node_id);
             for &edge_ix in &node.edges {
                 let edge = self.edge(edge_ix);
@@ -115,7 +137,7 @@ impl<'s> Graph<'s> {
         g
     }
 
-    pub fn node(&self, ix: NodeIx) -> &Node {
+    pub fn node(&self, ix: NodeIx) -> &Node<'s> {
         &self.nodes[ix.0 as usize]
     }
 
@@ -133,9 +155,26 @@ impl<'s> Graph<'s> {
     }
 
     pub fn connected(&self, a: NodeIx, b: NodeIx, depth: usize) -> bool {
-        let reach_a = self.bfs(a, depth.saturating_sub(1));
-        let reach_b = self.bfs(b, depth.saturating_sub(1));
-        reach_a.contains(&b) || reach_b.contains(&a)
+        let reach_a = self.bfs_bits(a, depth.saturating_sub(1));
+        let reach_b = self.bfs_bits(b, depth.saturating_sub(1));
+        reach_a.contains(b.0 as usize) || reach_b.contains(a.0 as usize)
+    }
+
+    /// `connected`, but for callers running many queries against a fixed graph: a
+    /// direct-adjacency check against `adjacency` (see `Graph::adjacency_bits`) answers
+    /// every `depth == 1` query in O(1) without paying for a BFS at all. Deeper queries
+    /// still fall back to `connected`'s BFS, since the matrix only records direct edges.
+    pub fn connected_with(
+        &self,
+        a: NodeIx,
+        b: NodeIx,
+        depth: usize,
+        adjacency: &bitset::BitMatrix,
+    ) -> bool {
+        if depth == 1 {
+            return a == b || self.is_adjacent(a, b, adjacency);
+        }
+        self.connected(a, b, depth)
     }
 
     pub fn direct_connection(&self, root: NodeIx, edge: NodeIx) -> Option<u16> {
@@ -174,18 +213,18 @@ impl<'s> Graph<'s> {
         2.0 * self.edges.len() as f32 / v
     }
 
-    fn kcore(&self) -> (usize, Graph<'_>) {
+    /// Iteratively peel nodes with fewer than `k` surviving neighbors until the
+    /// remaining set is stable. Returns the surviving original node indices (the k-core).
+    fn k_core_indices(&self, k: usize) -> HashSet<usize> {
         let mut retain = (0..self.nodes.len()).collect::<HashSet<usize>>();
         let mut degrees = self.nodes.iter().map(|n| n.edges.len()).collect::<Vec<_>>();
-        let mut k = 2;
 
-        let (k, nodes) = loop {
+        loop {
             let mut remove = Vec::new();
             retain = retain
                 .drain()
                 .filter(|&idx| {
                     if degrees[idx] < k {
-                        degrees[idx] = 0;
                         remove.push(idx);
                         false
                     } else {
@@ -194,20 +233,42 @@ impl<'s> Graph<'s> {
                 })
                 .collect();
 
-            if retain.is_empty() {
-                break (k - 1, remove);
+            if remove.is_empty() {
+                break;
             }
 
-            for idx in remove {
-                let node = &self.nodes[idx];
+            for idx in &remove {
+                let node = &self.nodes[*idx];
                 for &edge in &node.edges {
                     let e = self.edge(edge);
                     degrees[e.a.0 as usize] = degrees[e.a.0 as usize].saturating_sub(1);
                     degrees[e.b.0 as usize] = degrees[e.b.0 as usize].saturating_sub(1);
                 }
             }
-            k += 1;
-        };
+        }
+
+        retain
+    }
+
+    /// Find the highest-`k` non-empty k-core of this graph, i.e. the densest nested
+    /// shell surviving repeated peeling of low-degree nodes.
+    fn kcore(&self) -> (usize, Graph<'_>) {
+        let mut k = 2;
+        let mut nodes = self.k_core_indices(k);
+
+        if !nodes.is_empty() {
+            loop {
+                let next = self.k_core_indices(k + 1);
+                if next.is_empty() {
+                    break;
+                }
+                k += 1;
+                nodes = next;
+            }
+        } else {
+            k = 1;
+            nodes = (0..self.nodes.len()).collect();
+        }
 
         let mut g = Graph::default();
         let mut s = HashSet::new();
@@ -293,95 +354,34 @@ fn read_or_generate_weights<P: AsRef<std::path::Path>>(
     }
 }
 
-/// Pick a seed protein
-fn pick_seed(weights: &HashMap<String, f32>) -> &str {
-    let mut best = weights.iter().next().unwrap();
-    for (k, v) in weights {
-        if *v > *best.1 {
-            best = (k, v);
-        }
-    }
-    best.0
-}
-
-/// use the MCODE algorithm to assign a protein to a complex
-fn assign_complex<'s>(
-    graph: &Graph<'s>,
-    weights: &HashMap<String, f32>,
-    density: f32,
-) -> HashMap<&'s str, NodeIx> {
-    let mut membership = HashMap::new();
-    let mut complex_set = DisjointSet::new();
-    let mut stack = Vec::new();
-    let mut visited = HashSet::new();
-
-    let seed = graph.map[pick_seed(weights)];
-    stack.push(seed);
-
-    for ix in (0..graph.nodes.len() as u32).map(NodeIx) {
-        membership.insert(ix, complex_set.singleton(ix));
-    }
-
-    // save the last unvisited node id, so that we can traverse linearly
-    let mut ptr = NodeIx(0);
-    // outer loop, while we haven't visited every node in the graph
-    while visited.len() != graph.nodes.len() {
-        // depth-first traversal, starting from seed node
-        while let Some(nix) = stack.pop() {
-            visited.insert(nix);
-            let node = graph.node(nix);
-            for neighbor_ix in graph.neighbors(nix) {
-                let neighbor = graph.node(neighbor_ix);
-                if visited.insert(neighbor_ix) {
-                    if weights[neighbor.id] > (weights[node.id] * (1.0 - density)) {
-                        complex_set.union(|a, _| a, membership[&nix], membership[&neighbor_ix]);
-                    }
-                    stack.push(neighbor_ix);
-                }
-            }
-        }
-
-        for ix in (ptr.0..graph.nodes.len() as u32).map(NodeIx) {
-            if !visited.contains(&ix) {
-                ptr = ix;
-                break;
-            }
-        }
-        stack.push(ptr);
-    }
-
-    let mut complexes = HashMap::new();
-    for (ix, node) in graph.nodes.iter().enumerate() {
-        let element = membership[&NodeIx(ix as u32)];
-        complexes.insert(node.id, *complex_set.find(element));
-    }
-
-    complexes
-}
-
 fn main() -> io::Result<()> {
     let mut f = fs::File::open("data/cleaned.csv")?;
     let mut buffer = String::new();
     f.read_to_string(&mut buffer)?;
 
-    let mut g = Graph::with_capacity(25_000);
-    for line in buffer.lines().skip(1) {
-        let mut iter = line.split(',');
-        let a = iter.next().unwrap();
-        let b = iter.next().unwrap();
-        let w = iter.next().unwrap().parse::<u16>().unwrap();
-        if a == "unknown" || b == "unknown" {
-            continue;
-        }
-        g.add_edge(a, b, w);
-    }
+    // Drop the header row and any row naming an unmapped ("unknown") protein before
+    // handing the rest off to the generic edge-list parser.
+    let body = buffer
+        .lines()
+        .skip(1)
+        .filter(|line| {
+            let mut cols = line.split(',');
+            cols.next() != Some("unknown") && cols.next() != Some("unknown")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let g = Graph::from_edge_list(&body)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
 
     let weights = read_or_generate_weights("weights", &g)?;
-    let map = assign_complex(&g, &weights, 0.8);
+    let complexes = g.mcode(&weights, &mcode::McodeParams::default());
     let mut out = fs::File::create("output.tsv")?;
 
-    for (k, v) in map {
-        write!(out, "{}\t{}", k, v.0)?;
+    for (rank, complex) in complexes.iter().enumerate() {
+        for &node in &complex.nodes {
+            writeln!(out, "{}\t{}\t{:.4}", rank, g.node(node).id, complex.score())?;
+        }
     }
 
     Ok(())
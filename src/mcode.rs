@@ -0,0 +1,224 @@
+//! The full three-stage MCODE pipeline built on top of `Graph`'s vertex-weighting
+//! primitives (`Graph::weight`/`kcore`): seeded outward complex prediction, followed by
+//! haircut and fluff post-processing passes.
+
+use crate::{Graph, NodeIx};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// Tunables for stages 2 and 3 of MCODE. Vertex weighting (stage 1) is driven entirely
+/// by the caller-supplied `weights` map, typically produced via `Graph::weight`.
+#[derive(Copy, Clone, Debug)]
+pub struct McodeParams {
+    /// A neighbor is absorbed into the growing complex only if its weight exceeds
+    /// `(1 - vwp_threshold)` times the seed's weight (MCODE's "vertex weight percentage").
+    pub vwp_threshold: f32,
+    /// Strip nodes that fail the 2-core of each predicted complex's induced subgraph.
+    pub haircut: bool,
+    /// If set, pull in the unassigned direct neighbors of any complex member whose
+    /// local neighborhood density exceeds this value, without letting those neighbors
+    /// seed further growth.
+    pub fluff: Option<f32>,
+    /// Maximum number of hops a complex may grow outward from its seed.
+    pub max_depth: usize,
+}
+
+impl Default for McodeParams {
+    fn default() -> Self {
+        McodeParams {
+            vwp_threshold: 0.8,
+            haircut: true,
+            fluff: None,
+            max_depth: 100,
+        }
+    }
+}
+
+/// A predicted protein complex: a connected set of nodes plus the density of its
+/// induced subgraph.
+#[derive(Debug)]
+pub struct Complex {
+    pub nodes: Vec<NodeIx>,
+    pub density: f32,
+}
+
+impl Complex {
+    /// MCODE's ranking score: denser, larger complexes sort first.
+    pub fn score(&self) -> f32 {
+        self.density * self.nodes.len() as f32
+    }
+}
+
+impl<'s> Graph<'s> {
+    /// Run the full MCODE pipeline: seed outward from each unassigned node in
+    /// decreasing weight order, then haircut/fluff the result. Complexes are returned
+    /// ranked by `density * node_count`, highest first.
+    pub fn mcode(&self, weights: &HashMap<String, f32>, params: &McodeParams) -> Vec<Complex> {
+        let mut order: Vec<NodeIx> = (0..self.nodes.len() as u32).map(NodeIx).collect();
+        order.sort_by(|&a, &b| {
+            weight_of(self, weights, b)
+                .partial_cmp(&weight_of(self, weights, a))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let mut seen = HashSet::new();
+        let mut complexes = Vec::new();
+
+        for seed in order {
+            if seen.contains(&seed) {
+                continue;
+            }
+
+            let mut members = self.grow_complex(seed, weights, params, &seen);
+
+            if params.haircut {
+                self.haircut(&mut members);
+            }
+            if let Some(fluff) = params.fluff {
+                self.fluff(&mut members, fluff, &seen);
+            }
+
+            // Haircut can whittle a complex down below the seed itself (e.g. a
+            // seed+single-neighbor pair has no 2-core), so re-check size after
+            // post-processing rather than trusting `grow_complex`'s raw output.
+            if members.len() < 2 {
+                seen.insert(seed);
+                seen.extend(members.iter().copied());
+                continue;
+            }
+
+            seen.extend(members.iter().copied());
+
+            let density = self.induced_subgraph(&members).density();
+            complexes.push(Complex {
+                nodes: members.into_iter().collect(),
+                density,
+            });
+        }
+
+        complexes.sort_by(|a, b| b.score().partial_cmp(&a.score()).unwrap_or(Ordering::Equal));
+        complexes
+    }
+
+    /// Stage 2: seeded outward traversal. A neighbor is absorbed only if its weight
+    /// stays within `vwp_threshold` of the seed's, and only up to `max_depth` hops out.
+    fn grow_complex(
+        &self,
+        seed: NodeIx,
+        weights: &HashMap<String, f32>,
+        params: &McodeParams,
+        seen: &HashSet<NodeIx>,
+    ) -> HashSet<NodeIx> {
+        let seed_weight = weight_of(self, weights, seed);
+        let mut members = HashSet::new();
+        let mut depth = HashMap::new();
+        members.insert(seed);
+        depth.insert(seed, 0usize);
+        let mut stack = vec![seed];
+
+        while let Some(node_ix) = stack.pop() {
+            let d = depth[&node_ix];
+            if d >= params.max_depth {
+                continue;
+            }
+            for neighbor in self.neighbors(node_ix) {
+                if members.contains(&neighbor) || seen.contains(&neighbor) {
+                    continue;
+                }
+                if weight_of(self, weights, neighbor) > seed_weight * (1.0 - params.vwp_threshold) {
+                    members.insert(neighbor);
+                    depth.insert(neighbor, d + 1);
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        members
+    }
+
+    /// Stage 3a: drop peripheral nodes that fail the 2-core of the complex's induced
+    /// subgraph.
+    fn haircut(&self, members: &mut HashSet<NodeIx>) {
+        let sub = self.induced_subgraph(members);
+        let retained = sub.k_core_indices(2);
+        *members = retained
+            .into_iter()
+            .map(|idx| self.map[sub.nodes[idx].id])
+            .collect();
+    }
+
+    /// Stage 3b: for every member whose local neighborhood density exceeds `fluff`,
+    /// absorb its unassigned direct neighbors without letting them seed further growth.
+    fn fluff(&self, members: &mut HashSet<NodeIx>, fluff: f32, seen: &HashSet<NodeIx>) {
+        let dense_members: Vec<NodeIx> = members
+            .iter()
+            .copied()
+            .filter(|&m| self.subgraph(m).density() > fluff)
+            .collect();
+
+        for m in dense_members {
+            for neighbor in self.neighbors(m) {
+                if !seen.contains(&neighbor) {
+                    members.insert(neighbor);
+                }
+            }
+        }
+    }
+}
+
+fn weight_of(graph: &Graph, weights: &HashMap<String, f32>, ix: NodeIx) -> f32 {
+    weights.get(graph.node(ix).id).copied().unwrap_or(0.0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn haircut_drops_the_degree_one_leaf_after_growth() {
+        // Two triangles bridged by a single edge, plus a leaf hanging off the bridge.
+        // Equal weights mean grow_complex absorbs the whole connected component; the
+        // 2-core haircut must then prune the leaf, which has no 2-core membership.
+        let g = Graph::from_edge_list(
+            "a,b,1\nb,c,1\nc,a,1\nc,d,1\nd,e,1\ne,f,1\nf,d,1\nd,g,1",
+        )
+        .unwrap();
+        let weights: HashMap<String, f32> =
+            ["a", "b", "c", "d", "e", "f", "g"].iter().map(|&id| (id.to_string(), 1.0)).collect();
+
+        let complexes = g.mcode(&weights, &McodeParams::default());
+
+        assert_eq!(complexes.len(), 1, "the lone leaf shouldn't form its own complex");
+        let complex = &complexes[0];
+        assert_eq!(complex.nodes.len(), 6, "leaf 'g' must be dropped by haircut");
+        assert!(
+            !complex.nodes.iter().any(|&n| g.node(n).id == "g"),
+            "leaf 'g' survived haircut"
+        );
+    }
+
+    #[test]
+    fn complexes_are_ranked_by_density_times_node_count() {
+        // A dense 4-clique (high score) plus a disjoint sparse 3-path (low score);
+        // the clique must rank first regardless of discovery order.
+        let g = Graph::from_edge_list(
+            "a,b,1\na,c,1\na,d,1\nb,c,1\nb,d,1\nc,d,1\nx,y,1\ny,z,1",
+        )
+        .unwrap();
+        let weights: HashMap<String, f32> = ["a", "b", "c", "d", "x", "y", "z"]
+            .iter()
+            .map(|&id| (id.to_string(), 1.0))
+            .collect();
+
+        let params = McodeParams {
+            haircut: false,
+            ..McodeParams::default()
+        };
+        let complexes = g.mcode(&weights, &params);
+
+        assert_eq!(complexes.len(), 2);
+        assert!(complexes[0].score() >= complexes[1].score());
+        assert_eq!(complexes[0].nodes.len(), 4, "the clique should rank first");
+    }
+}
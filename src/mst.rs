@@ -0,0 +1,249 @@
+//! Minimum spanning tree construction and bottleneck-path ("strongest connecting
+//! interaction") queries over it, via heavy-light decomposition.
+
+use crate::disjoint::DisjointSet;
+use crate::{Edge, Graph, NodeIx};
+use std::collections::{HashMap, VecDeque};
+
+impl<'s> Graph<'s> {
+    /// Kruskal's algorithm: sort edges by weight and keep one only when its endpoints
+    /// were in different components. Node indices in the returned graph match `self`'s.
+    pub fn mst(&self) -> Graph<'s> {
+        let mut g = Graph::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            g.add_node(node.id);
+        }
+
+        let mut order: Vec<&Edge> = self.edges.iter().collect();
+        order.sort_by_key(|e| e.w);
+
+        let mut set = DisjointSet::new();
+        let mut membership = HashMap::new();
+        for ix in (0..self.nodes.len() as u32).map(NodeIx) {
+            membership.insert(ix, set.singleton(ix));
+        }
+
+        for edge in order {
+            if set.find_repr(membership[&edge.a]) != set.find_repr(membership[&edge.b]) {
+                let na = self.node(edge.a);
+                let nb = self.node(edge.b);
+                g.add_edge(na.id, nb.id, edge.w);
+                set.union(|a, _| a, membership[&edge.a], membership[&edge.b]);
+            }
+        }
+
+        g
+    }
+
+    /// The maximum edge weight on the unique path between `a` and `b` in the minimum
+    /// spanning tree, i.e. the strongest interaction bottlenecking their connection.
+    /// `None` if `a` and `b` fall in different MST components.
+    pub fn bottleneck(&self, a: NodeIx, b: NodeIx) -> Option<u16> {
+        let tree = self.mst();
+        Hld::build(&tree).query(a, b)
+    }
+}
+
+/// A flat, iterative max-segment-tree over `u16` weights.
+struct MaxSegTree {
+    n: usize,
+    tree: Vec<u16>,
+}
+
+impl MaxSegTree {
+    fn new(values: &[u16]) -> Self {
+        let n = values.len().max(1);
+        let mut tree = vec![0u16; 2 * n];
+        tree[n..n + values.len()].copy_from_slice(values);
+        for i in (1..n).rev() {
+            tree[i] = tree[2 * i].max(tree[2 * i + 1]);
+        }
+        MaxSegTree { n, tree }
+    }
+
+    /// Maximum over the inclusive range `[lo, hi]`. Returns 0 for an empty range.
+    fn query(&self, lo: usize, hi: usize) -> u16 {
+        if lo > hi {
+            return 0;
+        }
+        let (mut l, mut r) = (lo + self.n, hi + self.n + 1);
+        let mut result = 0u16;
+        while l < r {
+            if l & 1 == 1 {
+                result = result.max(self.tree[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                result = result.max(self.tree[r]);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        result
+    }
+}
+
+/// Heavy-light decomposition of a forest, laying each node into a position array so
+/// every heavy chain occupies a contiguous range, backed by a max-segment-tree over
+/// each node's edge weight to its parent.
+struct Hld {
+    parent: Vec<Option<NodeIx>>,
+    depth: Vec<usize>,
+    head: Vec<NodeIx>,
+    pos: Vec<usize>,
+    component: Vec<usize>,
+    seg: MaxSegTree,
+}
+
+impl Hld {
+    fn build(tree: &Graph) -> Self {
+        let n = tree.nodes.len();
+        let mut parent: Vec<Option<NodeIx>> = vec![None; n];
+        let mut depth = vec![0usize; n];
+        let mut component = vec![usize::MAX; n];
+        let mut order = Vec::with_capacity(n);
+        let mut comp_id = 0;
+
+        // First pass: BFS from every unvisited root to fix parent/depth/component and
+        // produce an order in which every node follows its parent.
+        for start in (0..n as u32).map(NodeIx) {
+            if component[start.0 as usize] != usize::MAX {
+                continue;
+            }
+            component[start.0 as usize] = comp_id;
+            order.push(start);
+            let mut queue = VecDeque::new();
+            queue.push_back(start);
+            while let Some(node) = queue.pop_front() {
+                for neighbor in tree.neighbors(node) {
+                    if component[neighbor.0 as usize] == usize::MAX {
+                        component[neighbor.0 as usize] = comp_id;
+                        parent[neighbor.0 as usize] = Some(node);
+                        depth[neighbor.0 as usize] = depth[node.0 as usize] + 1;
+                        order.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            comp_id += 1;
+        }
+
+        // Subtree sizes: process in reverse so every child is folded into its parent
+        // before the parent itself is visited.
+        let mut size = vec![1usize; n];
+        for &node in order.iter().rev() {
+            if let Some(p) = parent[node.0 as usize] {
+                size[p.0 as usize] += size[node.0 as usize];
+            }
+        }
+
+        // Heavy child: the child with the largest subtree.
+        let mut heavy: Vec<Option<NodeIx>> = vec![None; n];
+        for &node in &order {
+            if let Some(p) = parent[node.0 as usize] {
+                let p_idx = p.0 as usize;
+                let is_heavier = match heavy[p_idx] {
+                    None => true,
+                    Some(h) => size[node.0 as usize] > size[h.0 as usize],
+                };
+                if is_heavier {
+                    heavy[p_idx] = Some(node);
+                }
+            }
+        }
+
+        // Second pass: iterative preorder DFS that always descends into the heavy
+        // child first, so each heavy chain lands in a contiguous `pos` range.
+        let mut pos = vec![0usize; n];
+        let mut head = vec![NodeIx(0); n];
+        let mut edge_weight_at_pos = vec![0u16; n];
+        let mut counter = 0usize;
+
+        for &root in order.iter().filter(|&&n| parent[n.0 as usize].is_none()) {
+            let mut stack = vec![(root, root)];
+            while let Some((node, chain_head)) = stack.pop() {
+                pos[node.0 as usize] = counter;
+                head[node.0 as usize] = chain_head;
+                if let Some(p) = parent[node.0 as usize] {
+                    edge_weight_at_pos[counter] = tree.direct_connection(node, p).unwrap_or(0);
+                }
+                counter += 1;
+
+                let mut heavy_child = None;
+                for neighbor in tree.neighbors(node) {
+                    if parent[neighbor.0 as usize] != Some(node) {
+                        continue;
+                    }
+                    if heavy[node.0 as usize] == Some(neighbor) {
+                        heavy_child = Some(neighbor);
+                    } else {
+                        stack.push((neighbor, neighbor));
+                    }
+                }
+                if let Some(hc) = heavy_child {
+                    stack.push((hc, chain_head));
+                }
+            }
+        }
+
+        Hld {
+            parent,
+            depth,
+            head,
+            pos,
+            component,
+            seg: MaxSegTree::new(&edge_weight_at_pos),
+        }
+    }
+
+    fn query(&self, mut a: NodeIx, mut b: NodeIx) -> Option<u16> {
+        if self.component[a.0 as usize] != self.component[b.0 as usize] {
+            return None;
+        }
+
+        let mut result = 0u16;
+        while self.head[a.0 as usize] != self.head[b.0 as usize] {
+            if self.depth[self.head[a.0 as usize].0 as usize] < self.depth[self.head[b.0 as usize].0 as usize] {
+                std::mem::swap(&mut a, &mut b);
+            }
+            let h = self.head[a.0 as usize];
+            result = result.max(self.seg.query(self.pos[h.0 as usize], self.pos[a.0 as usize]));
+            a = self.parent[h.0 as usize].expect("a chain head always has a parent until a == b");
+        }
+
+        if a != b {
+            let (shallow, deep) = if self.depth[a.0 as usize] < self.depth[b.0 as usize] {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            result = result.max(self.seg.query(self.pos[shallow.0 as usize] + 1, self.pos[deep.0 as usize]));
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+
+    #[test]
+    fn bottleneck_is_the_max_weight_on_the_mst_path() {
+        let g = Graph::from_edge_list("a,b,1\nb,c,5\na,c,10\nc,d,2").unwrap();
+        let a = g.map["a"];
+        let d = g.map["d"];
+        // MST keeps a-b(1), c-d(2), b-c(5) and drops a-c(10); the a..d path's
+        // strongest link is the b-c edge.
+        assert_eq!(g.bottleneck(a, d), Some(5));
+    }
+
+    #[test]
+    fn bottleneck_is_none_across_components() {
+        let g = Graph::from_edge_list("a,b,1\nc,d,1").unwrap();
+        let a = g.map["a"];
+        let c = g.map["c"];
+        assert_eq!(g.bottleneck(a, c), None);
+    }
+}
@@ -0,0 +1,190 @@
+//! Pluggable `Graph` constructors for the formats this crate is fed, beyond the
+//! bundled PPI CSV: a plain `a,b,w` edge list, and the standard adjacency-matrix
+//! benchmark format.
+
+use crate::Graph;
+use std::fmt;
+
+/// Why a `from_edge_list`/`from_adjacency_matrix` parse failed.
+#[derive(Debug)]
+pub enum ParseError {
+    /// An edge-list row didn't have exactly `a,b,w` columns.
+    RaggedEdge { row: usize, found: usize },
+    /// An adjacency-matrix row had a different column count than the matrix has rows.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A cell that should hold a non-negative edge weight didn't parse as one.
+    InvalidValue {
+        row: usize,
+        col: usize,
+        value: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::RaggedEdge { row, found } => {
+                write!(f, "row {row}: expected 3 columns (a,b,w), found {found}")
+            }
+            ParseError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {row}: expected {expected} columns to match the matrix's row count, found {found}"
+            ),
+            ParseError::InvalidValue { row, col, value } => write!(
+                f,
+                "row {row}, col {col}: expected a non-negative integer weight, found {value:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl<'s> Graph<'s> {
+    /// Parse a plain `a,b,w` edge list (no header row), borrowing node ids directly
+    /// from `input`.
+    pub fn from_edge_list(input: &'s str) -> Result<Graph<'s>, ParseError> {
+        let mut g = Graph::default();
+        for (row, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut cols = line.split(',');
+            let a = cols.next().ok_or(ParseError::RaggedEdge { row, found: 0 })?;
+            let b = cols.next().ok_or(ParseError::RaggedEdge { row, found: 1 })?;
+            let w = cols.next().ok_or(ParseError::RaggedEdge { row, found: 2 })?;
+            if cols.next().is_some() {
+                return Err(ParseError::RaggedEdge {
+                    row,
+                    found: 4 + cols.count(),
+                });
+            }
+            let w: u16 = w.trim().parse().map_err(|_| ParseError::InvalidValue {
+                row,
+                col: 2,
+                value: w.to_string(),
+            })?;
+            g.add_edge(a.trim(), b.trim(), w);
+        }
+        Ok(g)
+    }
+
+    /// Parse whitespace-separated rows of `0`/`1` (or `0`/weight) where a nonzero entry
+    /// at row `i`, column `j` adds an edge between node `i` and node `j`. Every cell is
+    /// validated, but only the upper triangle adds an edge, since edges in this crate
+    /// are undirected.
+    ///
+    /// `labels`, if given, must have one entry per row/column and is borrowed directly
+    /// for the resulting node ids; otherwise labels are auto-generated as `"0"`, `"1"`,
+    /// `"2"`, ... and each leaked exactly once, since nothing in `input` can be
+    /// borrowed for them.
+    pub fn from_adjacency_matrix(
+        input: &'s str,
+        labels: Option<&[&'s str]>,
+    ) -> Result<Graph<'s>, ParseError> {
+        let rows: Vec<Vec<&str>> = input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        let n = rows.len();
+
+        let generated: Vec<&'s str> = if labels.is_none() {
+            (0..n)
+                .map(|i| -> &'s str { Box::leak(i.to_string().into_boxed_str()) })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let label_for = |idx: usize| -> &'s str {
+            match labels {
+                Some(ls) => ls[idx],
+                None => generated[idx],
+            }
+        };
+
+        let mut g = Graph::with_capacity(n);
+        for i in 0..n {
+            g.add_node(label_for(i));
+        }
+
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != n {
+                return Err(ParseError::RaggedRow {
+                    row: i,
+                    expected: n,
+                    found: row.len(),
+                });
+            }
+            for (j, &cell) in row.iter().enumerate() {
+                let weight: u16 = cell.parse().map_err(|_| ParseError::InvalidValue {
+                    row: i,
+                    col: j,
+                    value: cell.to_string(),
+                })?;
+                // Every cell is validated, but only the upper triangle adds an edge
+                // (edges in this crate are undirected, so the lower triangle would
+                // just duplicate it).
+                if j > i && weight != 0 {
+                    g.add_edge(label_for(i), label_for(j), weight);
+                }
+            }
+        }
+
+        Ok(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ragged_edge_row_is_rejected() {
+        let err = Graph::from_edge_list("a,b,1\nc,d").unwrap_err();
+        assert!(matches!(err, ParseError::RaggedEdge { row: 1, found: 2 }));
+    }
+
+    #[test]
+    fn non_numeric_edge_weight_is_rejected() {
+        let err = Graph::from_edge_list("a,b,heavy").unwrap_err();
+        assert!(matches!(err, ParseError::InvalidValue { row: 0, col: 2, .. }));
+    }
+
+    #[test]
+    fn ragged_adjacency_matrix_row_is_rejected() {
+        let err = Graph::from_adjacency_matrix("0 1\n1 0 0", None).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::RaggedRow {
+                row: 1,
+                expected: 2,
+                found: 3
+            }
+        ));
+    }
+
+    #[test]
+    fn non_numeric_adjacency_matrix_cell_is_rejected() {
+        let err = Graph::from_adjacency_matrix("0 1\nbad 0", None).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidValue { row: 1, col: 0, .. }));
+    }
+
+    #[test]
+    fn auto_generated_labels_are_stable_across_nodes_and_edges() {
+        let g = Graph::from_adjacency_matrix("0 1\n1 0", None).unwrap();
+        // Node creation and edge creation must resolve to the *same* leaked "0"/"1"
+        // strings, not fresh leaks that happen to compare equal by content.
+        assert_eq!(g.node(g.map["0"]).id, "0");
+        assert_eq!(g.node(g.map["1"]).id, "1");
+        assert!(g.direct_connection(g.map["0"], g.map["1"]).is_some());
+    }
+}